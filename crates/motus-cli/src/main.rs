@@ -56,6 +56,106 @@ enum Commands {
         #[arg(short, long, default_value = "7")]
         numbers: u32
     },
+
+    #[command(name = "memorable")]
+    #[command(about = "Generate a memorable passphrase")]
+    #[command(
+    long_about = "Generate a memorable passphrase by combining a configurable number of random words."
+    )]
+    Memorable {
+        /// 指定密码短语中包含的单词数量
+        #[arg(short, long, default_value = "4", value_parser = validate_word_count)]
+        words: u32,
+
+        /// 用于分隔单词的字符
+        #[arg(short, long, default_value = "-")]
+        separator: char,
+
+        /// 将每个单词的首字母大写
+        #[arg(short, long)]
+        capitalize: bool,
+
+        /// 在密码短语中插入一个随机数字或符号
+        #[arg(short = 'x', long = "extra")]
+        extra: bool,
+    },
+
+    #[command(name = "random")]
+    #[command(about = "Generate a random password")]
+    #[command(
+    long_about = "Generate a random password with a configurable length and set of character classes."
+    )]
+    Random {
+        /// 指定生成的密码的字符数
+        #[arg(short, long, default_value = "16", value_parser = validate_character_count)]
+        characters: u32,
+
+        /// 从密码中排除大写字母
+        #[arg(long = "no-uppercase")]
+        no_uppercase: bool,
+
+        /// 从密码中排除小写字母
+        #[arg(long = "no-lowercase")]
+        no_lowercase: bool,
+
+        /// 在密码中包含数字
+        #[arg(short, long)]
+        numbers: bool,
+
+        /// 在密码中包含符号
+        #[arg(short, long)]
+        symbols: bool,
+
+        /// 从字符池中剔除形近字符(如 'i'/'l'/'1'、'o'/'0')
+        #[arg(long = "no-ambiguous")]
+        no_ambiguous: bool,
+    },
+
+    #[command(name = "derive")]
+    #[command(about = "Deterministically derive a password from a master password and a site")]
+    #[command(
+    long_about = "Derive a password from a master password and a site identifier, with no locally stored state. Running the same inputs again always reproduces the exact same password."
+    )]
+    Derive {
+        /// 密码所对应的站点标识符(例如域名)
+        site: String,
+
+        /// 该站点上使用的登录名/用户名
+        #[arg(short, long, default_value = "")]
+        login: String,
+
+        /// 允许为同一站点派生出多个不同密码的计数器
+        #[arg(long, default_value = "1")]
+        counter: u32,
+
+        /// 指定生成的密码的字符数
+        #[arg(short, long, default_value = "16", value_parser = validate_character_count)]
+        characters: u32,
+
+        /// 从密码中排除大写字母
+        #[arg(long = "no-uppercase")]
+        no_uppercase: bool,
+
+        /// 从密码中排除小写字母
+        #[arg(long = "no-lowercase")]
+        no_lowercase: bool,
+
+        /// 在密码中包含数字
+        #[arg(short, long)]
+        numbers: bool,
+
+        /// 在密码中包含符号
+        #[arg(short, long)]
+        symbols: bool,
+
+        /// 用于派生密码的哈希算法
+        #[arg(short, long, default_value = "sha256", value_enum)]
+        algorithm: motus::HashAlgorithm,
+
+        /// 从字符池中剔除形近字符(如 'i'/'l'/'1'、'o'/'0')
+        #[arg(long = "no-ambiguous")]
+        no_ambiguous: bool,
+    },
 }
 
 fn main() {
@@ -75,6 +175,33 @@ fn main() {
 
     let password = match opts.command {
         Commands::Pin {numbers} => motus::pin_password(&mut rng, numbers),
+        Commands::Memorable {words, separator, capitalize, extra} => {
+            motus::memorable_password(&mut rng, words, separator, capitalize, extra)
+        }
+        Commands::Random {characters, no_uppercase, no_lowercase, numbers, symbols, no_ambiguous} => {
+            let character_set = character_set_from_flags(no_uppercase, no_lowercase, numbers, symbols);
+
+            motus::random_password(&mut rng, characters, character_set, no_ambiguous)
+                .expect("characters should be enough for the selected character classes")
+        }
+        Commands::Derive {ref site, ref login, counter, characters, no_uppercase, no_lowercase, numbers, symbols, algorithm, no_ambiguous} => {
+            let master_password = rpassword::prompt_password("Master password: ")
+                .expect("unable to read master password");
+
+            let character_set = character_set_from_flags(no_uppercase, no_lowercase, numbers, symbols);
+
+            motus::derive_password(
+                &master_password,
+                site,
+                login,
+                counter,
+                algorithm,
+                characters,
+                character_set,
+                no_ambiguous,
+            )
+            .expect("characters should be enough for the selected character classes")
+        }
     };
 
     // 将密码复制到剪贴板
@@ -89,7 +216,7 @@ fn main() {
     match opts.output {
         OutputFormat::Text => {
             if opts.analyze {
-                let analysis = SecurityAnalysis::new(&password);
+                let analysis = SecurityAnalysis::new(&password, entropy_source(&opts.command));
                 analysis.display_report(TableStyle::extended(), 80)
             } else {
                 println!("{}", password);
@@ -99,10 +226,13 @@ fn main() {
             let output = PasswordOutput{
                 kind: match opts.command {
                     Commands::Pin {..} => PasswordKind::Pin,
+                    Commands::Memorable {..} => PasswordKind::Memorable,
+                    Commands::Random {..} => PasswordKind::Random,
+                    Commands::Derive {..} => PasswordKind::Derive,
                 },
                 password: &password,
                 analysis: if opts.analyze {
-                    Some(SecurityAnalysis::new(&password))
+                    Some(SecurityAnalysis::new(&password, entropy_source(&opts.command)))
                 } else {
                     None
                 },
@@ -113,6 +243,56 @@ fn main() {
 
 }
 
+/// 根据生成该密码所用的子命令及其参数，得到对应的 [`EntropySource`]，
+/// 以便在不依赖 zxcvbn 攻击模型的情况下估算密码的理论信息熵。
+fn entropy_source(command: &Commands) -> EntropySource {
+    match command {
+        Commands::Pin {..} => EntropySource::CharacterPool { pool_size: 10 },
+        Commands::Memorable {words, ..} => EntropySource::Wordlist {
+            wordlist_size: motus::wordlist_size(),
+            word_count: *words,
+        },
+        Commands::Random {no_uppercase, no_lowercase, numbers, symbols, no_ambiguous, ..}
+        | Commands::Derive {no_uppercase, no_lowercase, numbers, symbols, no_ambiguous, ..} => {
+            EntropySource::CharacterPool {
+                pool_size: motus::character_pool_size(
+                    character_set_from_flags(*no_uppercase, *no_lowercase, *numbers, *symbols),
+                    *no_ambiguous,
+                ),
+            }
+        }
+    }
+}
+
+/// 根据 `--no-uppercase`/`--no-lowercase`/`--numbers`/`--symbols` 标志构建
+/// [`motus::CharacterSet`]；大小写字母默认启用，可分别通过对应的 `--no-*` 标志禁用。
+fn character_set_from_flags(
+    no_uppercase: bool,
+    no_lowercase: bool,
+    numbers: bool,
+    symbols: bool,
+) -> motus::CharacterSet {
+    let mut character_set = motus::CharacterSet::empty();
+
+    if !no_uppercase {
+        character_set |= motus::CharacterSet::UPPERCASE;
+    }
+
+    if !no_lowercase {
+        character_set |= motus::CharacterSet::LOWERCASE;
+    }
+
+    if numbers {
+        character_set |= motus::CharacterSet::NUMBERS;
+    }
+
+    if symbols {
+        character_set |= motus::CharacterSet::SYMBOLS;
+    }
+
+    character_set
+}
+
 #[derive(ValueEnum, Clone,Debug)]
 enum OutputFormat {
     Text,
@@ -132,12 +312,42 @@ struct PasswordOutput<'a> {
 #[serde(rename_all = "lowercase")]
 enum PasswordKind {
     Pin,
+    Memorable,
+    Random,
+    Derive,
 }
 
 
+/// 描述密码是如何生成的，使我们可以在不依赖 zxcvbn 攻击模型的情况下
+/// 计算出一个 Shannon/池熵的理论估计值。
+enum EntropySource {
+    /// 从一个大小为 `pool_size` 的字符池中独立抽取每一个字符。
+    CharacterPool { pool_size: usize },
+
+    /// 从一个大小为 `wordlist_size` 的词表中独立抽取每一个单词。
+    Wordlist { wordlist_size: usize, word_count: u32 },
+}
+
+impl EntropySource {
+    /// 计算 `bits = length * log2(pool_size)`(字符模式)或
+    /// `bits = word_count * log2(wordlist_size)`(单词模式)。
+    fn bits_entropy(&self, password: &str) -> f64 {
+        match self {
+            EntropySource::CharacterPool { pool_size } => {
+                password.chars().count() as f64 * (*pool_size as f64).log2()
+            }
+            EntropySource::Wordlist {
+                wordlist_size,
+                word_count,
+            } => *word_count as f64 * (*wordlist_size as f64).log2(),
+        }
+    }
+}
+
 struct SecurityAnalysis<'a> {
     password: &'a str,
     entropy: zxcvbn::Entropy,
+    bits_entropy: f64,
 }
 
 
@@ -145,6 +355,9 @@ impl Display for PasswordKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             PasswordKind::Pin => write!(f,"pin"),
+            PasswordKind::Memorable => write!(f,"memorable"),
+            PasswordKind::Random => write!(f,"random"),
+            PasswordKind::Derive => write!(f,"derive"),
         }
     }
 }
@@ -187,7 +400,7 @@ impl Serialize for SecurityAnalysis<'_> {
                 .to_string(),
         );
 
-        let mut struct_serializer = serializer.serialize_struct("SecurityAnalysis",3)?;
+        let mut struct_serializer = serializer.serialize_struct("SecurityAnalysis",4)?;
         struct_serializer.serialize_field(
             "strength",
             &PasswordStrength::from(self.entropy.score()).to_string(),
@@ -198,14 +411,20 @@ impl Serialize for SecurityAnalysis<'_> {
             format!("10^{:.0}", &self.entropy.guesses_log10()).as_str(),
         )?;
         struct_serializer.serialize_field("crack_times", &crack_times)?;
+        struct_serializer.serialize_field("bits_entropy", &self.bits_entropy)?;
         struct_serializer.end()
     }
 }
 
 impl <'a> SecurityAnalysis<'a> {
-    fn new(password: &'a str) -> Self {
+    fn new(password: &'a str, entropy_source: EntropySource) -> Self {
         let entropy = zxcvbn(password, &[]).expect("unable to analyze password's safety");
-        Self { password, entropy }
+        let bits_entropy = entropy_source.bits_entropy(password);
+        Self {
+            password,
+            entropy,
+            bits_entropy,
+        }
     }
 
     fn display_report(&self, table_style: TableStyle, max_width: usize) {
@@ -259,6 +478,15 @@ impl <'a> SecurityAnalysis<'a> {
             ),
         ]));
 
+        table.add_row(Row::new(vec![
+            TableCell::new("Pool Entropy".bold()),
+            TableCell::new_with_alignment(
+                format!("{:.1} bits", self.bits_entropy),
+                1,
+                Alignment::Left,
+            ),
+        ]));
+
         println!("{}", table.render());
     }
 
@@ -426,4 +654,19 @@ mod tests {
         assert!(validate_pin_length("12").is_ok());
         assert!(validate_pin_length("13").is_err());
     }
+
+    #[test]
+    fn test_bits_entropy_character_pool() {
+        let source = EntropySource::CharacterPool { pool_size: 16 };
+        assert_eq!(source.bits_entropy("aaaa"), 4.0 * 16.0_f64.log2());
+    }
+
+    #[test]
+    fn test_bits_entropy_wordlist() {
+        let source = EntropySource::Wordlist {
+            wordlist_size: 1024,
+            word_count: 4,
+        };
+        assert_eq!(source.bits_entropy("any-password"), 4.0 * 1024.0_f64.log2());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,214 @@
+use std::fmt::{self, Display, Formatter};
+
+use clap::ValueEnum;
+use hmac::Hmac;
+use pbkdf2::pbkdf2;
+use sha2::{Sha256, Sha384, Sha512};
+
+use crate::{flat_character_pool, required_character_classes, CharacterSet, PasswordError};
+
+const ITERATIONS: u32 = 100_000;
+const DERIVED_KEY_LENGTH: usize = 32;
+
+/// PBKDF2 所使用的哈希算法，决定了 [`derive_password`] 的输出，
+/// 因此必须和最初生成密码时使用的算法保持一致。
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Display for HashAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgorithm::Sha256 => write!(f, "sha256"),
+            HashAlgorithm::Sha384 => write!(f, "sha384"),
+            HashAlgorithm::Sha512 => write!(f, "sha512"),
+        }
+    }
+}
+
+/// 根据主密码和站点信息确定性地派生出密码，不需要任何本地存储的状态:
+/// 只要再次提供相同的主密码、站点、登录名和计数器，就能重现出完全相同的密码
+/// (LessPass/Master Password 方案)。
+///
+/// 派生过程为: 用 PBKDF2-HMAC 以 `site`、`login` 依次加上长度前缀、再接上大端表示的
+/// `counter` 拼接成的盐值(长度前缀避免例如 `site="fo", login="o"` 与 `site="foo", login=""` 产生相同的盐值)对
+/// `master_password` 做 100,000 轮迭代，得到的字节串被视为一个大端表示的大整数
+/// ("entropy")。随后反复对 `pool.len()` 取模来选出字符、再将 entropy 除以
+/// `pool.len()`，凑够 `characters` 个字符；最后为每一个被选中的字符类别
+/// 再各消耗两个值，从尚未被其他类别占用的位置中选出一个并覆写为该类别的字符，
+/// 保证该类别至少出现一次、且不会顶掉另一个类别刚刚写入的保证字符。
+///
+/// # Arguments
+///
+/// * `master_password: &str` - 用户记忆的主密码
+/// * `site: &str` - 密码所对应的站点标识符(例如域名)
+/// * `login: &str` - 该站点上使用的登录名/用户名
+/// * `counter: u32` - 允许为同一站点派生出多个不同密码的计数器
+/// * `algorithm: HashAlgorithm` - PBKDF2 使用的哈希算法
+/// * `characters: u32` - 密码所需的字符数
+/// * `character_set: CharacterSet` - 密码中应包含的字符类别
+/// * `no_ambiguous: bool` - 指示是否应从字符池中剔除形近字符(如 'i'/'l'/'1'、'o'/'0')的标志
+///
+/// # Returns
+///
+/// * `Result<String, PasswordError>` - 派生出的密码，如果 `characters`
+///   小于已启用的字符类别数量，则返回错误
+#[allow(clippy::too_many_arguments)]
+pub fn derive_password(
+    master_password: &str,
+    site: &str,
+    login: &str,
+    counter: u32,
+    algorithm: HashAlgorithm,
+    characters: u32,
+    character_set: CharacterSet,
+    no_ambiguous: bool,
+) -> Result<String, PasswordError> {
+    let required_classes = required_character_classes(character_set, no_ambiguous);
+
+    if (characters as usize) < required_classes.len() {
+        return Err(PasswordError::NotEnoughCharacters {
+            characters,
+            required_classes: required_classes.len(),
+        });
+    }
+
+    let mut salt = Vec::new();
+    for field in [site.as_bytes(), login.as_bytes()] {
+        salt.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        salt.extend_from_slice(field);
+    }
+    salt.extend_from_slice(&counter.to_be_bytes());
+
+    let mut entropy = vec![0u8; DERIVED_KEY_LENGTH];
+    match algorithm {
+        HashAlgorithm::Sha256 => {
+            pbkdf2::<Hmac<Sha256>>(master_password.as_bytes(), &salt, ITERATIONS, &mut entropy)
+        }
+        HashAlgorithm::Sha384 => {
+            pbkdf2::<Hmac<Sha384>>(master_password.as_bytes(), &salt, ITERATIONS, &mut entropy)
+        }
+        HashAlgorithm::Sha512 => {
+            pbkdf2::<Hmac<Sha512>>(master_password.as_bytes(), &salt, ITERATIONS, &mut entropy)
+        }
+    }
+    .expect("HMAC can be initialized with any key length");
+
+    let pool = flat_character_pool(character_set, no_ambiguous);
+
+    let mut password: Vec<char> = (0..characters)
+        .map(|_| {
+            let index = divmod_big_endian(&mut entropy, pool.len() as u32);
+            pool[index as usize]
+        })
+        .collect();
+
+    let mut available_positions: Vec<usize> = (0..password.len()).collect();
+
+    for class_pool in &required_classes {
+        let char_index = divmod_big_endian(&mut entropy, class_pool.len() as u32);
+        let position_index = divmod_big_endian(&mut entropy, available_positions.len() as u32);
+        let position = available_positions.remove(position_index as usize);
+        password[position] = class_pool[char_index as usize];
+    }
+
+    Ok(password.into_iter().collect())
+}
+
+/// 将 `value` 视为大端表示的大整数，原地将其除以 `divisor`，并返回余数。
+fn divmod_big_endian(value: &mut [u8], divisor: u32) -> u32 {
+    let mut remainder: u64 = 0;
+
+    for byte in value.iter_mut() {
+        let current = (remainder << 8) | (*byte as u64);
+        *byte = (current / divisor as u64) as u8;
+        remainder = current % divisor as u64;
+    }
+
+    remainder as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn derive(site: &str, login: &str, counter: u32) -> String {
+        derive_password(
+            "masterpw",
+            site,
+            login,
+            counter,
+            HashAlgorithm::Sha256,
+            16,
+            CharacterSet::ALL,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_derive_password_is_deterministic() {
+        assert_eq!(derive("example.com", "alice", 1), derive("example.com", "alice", 1));
+    }
+
+    #[test]
+    fn test_derive_password_differs_by_site() {
+        assert_ne!(derive("example.com", "alice", 1), derive("example.org", "alice", 1));
+    }
+
+    #[test]
+    fn test_derive_password_differs_by_login() {
+        assert_ne!(derive("example.com", "alice", 1), derive("example.com", "bob", 1));
+    }
+
+    #[test]
+    fn test_derive_password_differs_by_counter() {
+        assert_ne!(derive("example.com", "alice", 1), derive("example.com", "alice", 2));
+    }
+
+    #[test]
+    fn test_derive_password_differs_by_algorithm() {
+        let sha256 = derive_password(
+            "masterpw",
+            "example.com",
+            "alice",
+            1,
+            HashAlgorithm::Sha256,
+            16,
+            CharacterSet::ALL,
+            false,
+        )
+        .unwrap();
+        let sha512 = derive_password(
+            "masterpw",
+            "example.com",
+            "alice",
+            1,
+            HashAlgorithm::Sha512,
+            16,
+            CharacterSet::ALL,
+            false,
+        )
+        .unwrap();
+
+        assert_ne!(sha256, sha512);
+    }
+
+    #[test]
+    fn test_derive_password_does_not_conflate_site_and_login_boundary() {
+        assert_ne!(derive("fo", "o", 1), derive("foo", "", 1));
+    }
+
+    #[test]
+    fn test_derive_password_contains_every_selected_class() {
+        let required_classes = required_character_classes(CharacterSet::ALL, false);
+        let password: Vec<char> = derive("example.com", "alice", 1).chars().collect();
+
+        for pool in &required_classes {
+            assert!(pool.iter().any(|c| password.contains(c)));
+        }
+    }
+}
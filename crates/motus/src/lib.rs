@@ -1,11 +1,18 @@
 use std::sync::Arc;
 
+use bitflags::bitflags;
 use clap::ValueEnum;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use rand::distributions::{Uniform, WeightedIndex};
 use rand::prelude::*;
 
+mod derive;
+mod words;
+
+pub use derive::{derive_password, HashAlgorithm};
+use words::WORDLIST;
+
 pub fn pin_password<R:Rng>(rng: &mut R, numbers: u32) -> String {
     (0..numbers)
         .map(|_| NUMBER_CHARS[rng.gen_range(0..NUMBER_CHARS.len())])
@@ -15,73 +22,265 @@ pub fn pin_password<R:Rng>(rng: &mut R, numbers: u32) -> String {
 
 const NUMBER_CHARS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
 
-// LETTER_CHARS是可用于密码的数字列表
-const LETTER_CHARS: &[char] = &[
+// LOWERCASE_CHARS 是可用于密码的小写字母列表
+const LOWERCASE_CHARS: &[char] = &[
     'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
-    't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L',
-    'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+// UPPERCASE_CHARS 是可用于密码的大写字母列表
+const UPPERCASE_CHARS: &[char] = &[
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
 ];
 
 // SYMBOL_CHARS 可用于密码的符号列表
 const SYMBOL_CHARS: &[char] = &['!', '@', '#', '$', '%', '^', '&', '*', '(', ')'];
 
-/// 生成具有指定长度和可选包含数字和符号的随机密码。
+// AMBIGUOUS_CHARS 是容易被看错或读错的形近字符，`--no-ambiguous` 会将它们从各个
+// 字符池中剔除，例如 'i'/'l'/'1'、'o'/'0' 以及括号类符号。
+const AMBIGUOUS_CHARS: &[char] = &['i', 'l', '1', 'L', 'o', '0', 'O', '(', ')'];
+
+/// 从 `pool` 中剔除 [`AMBIGUOUS_CHARS`] 里列出的形近字符(当 `no_ambiguous` 为真时)。
+fn filter_ambiguous(pool: &'static [char], no_ambiguous: bool) -> Vec<char> {
+    if no_ambiguous {
+        pool.iter()
+            .copied()
+            .filter(|c| !AMBIGUOUS_CHARS.contains(c))
+            .collect()
+    } else {
+        pool.to_vec()
+    }
+}
+
+/// 返回所选字符类别中全部字符拼接而成的扁平字符池(大小写字母、数字、符号依次排列)。
+/// 当 `no_ambiguous` 为真时，会先从每个类别中剔除形近字符。
+pub(crate) fn flat_character_pool(character_set: CharacterSet, no_ambiguous: bool) -> Vec<char> {
+    let mut pool = Vec::new();
+
+    if character_set.contains(CharacterSet::UPPERCASE) {
+        pool.extend(filter_ambiguous(UPPERCASE_CHARS, no_ambiguous));
+    }
+
+    if character_set.contains(CharacterSet::LOWERCASE) {
+        pool.extend(filter_ambiguous(LOWERCASE_CHARS, no_ambiguous));
+    }
+
+    if character_set.contains(CharacterSet::NUMBERS) {
+        pool.extend(filter_ambiguous(NUMBER_CHARS, no_ambiguous));
+    }
+
+    if character_set.contains(CharacterSet::SYMBOLS) {
+        pool.extend(filter_ambiguous(SYMBOL_CHARS, no_ambiguous));
+    }
+
+    pool
+}
+
+/// 返回所选字符类别(经 `no_ambiguous` 过滤后)的字符池大小。
 ///
-/// 此函数创建具有所需字符数的随机密码
-/// 根据提供的布尔标志，生成的密码可以包括字母、数字和符号。
+/// 这个数字就是 Shannon/池熵公式 `bits = length * log2(pool_size)` 中的
+/// `pool_size`，可用于在不依赖 zxcvbn 攻击模型的情况下估算密码的理论信息熵。
+pub fn character_pool_size(character_set: CharacterSet, no_ambiguous: bool) -> usize {
+    flat_character_pool(character_set, no_ambiguous).len()
+}
+
+/// 返回内置词表中的单词数量，用于估算 [`memorable_password`] 生成的密码短语的
+/// Shannon/池熵: `bits = word_count * log2(wordlist_size)`。
+pub fn wordlist_size() -> usize {
+    WORDLIST.len()
+}
+
+bitflags! {
+    /// 密码生成时可启用的字符类别。
+    ///
+    /// 各标志可以自由组合，例如 `CharacterSet::LOWERCASE | CharacterSet::NUMBERS`。
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct CharacterSet: u8 {
+        const UPPERCASE = 0b0001;
+        const LOWERCASE = 0b0010;
+        const NUMBERS   = 0b0100;
+        const SYMBOLS   = 0b1000;
+
+        /// 大小写字母的组合，等价于 `UPPERCASE | LOWERCASE`。
+        const LETTERS = Self::UPPERCASE.bits() | Self::LOWERCASE.bits();
+
+        /// 所有受支持的字符类别。
+        const ALL = Self::LETTERS.bits() | Self::NUMBERS.bits() | Self::SYMBOLS.bits();
+    }
+}
+
+impl CharacterSet {
+    /// 返回该集合中已启用类别对应的字符池，按 字母/数字/符号 的顺序排列，
+    /// 每个类别最多对应一个字符池(大小写字母会合并为同一个"字母"池)。
+    /// 当 `no_ambiguous` 为真时，每个字符池都会先剔除形近字符再返回。
+    fn char_pools(&self, no_ambiguous: bool) -> Vec<Vec<char>> {
+        let mut pools = Vec::new();
+        let mut letters = Vec::new();
+
+        if self.contains(CharacterSet::UPPERCASE) {
+            letters.extend(filter_ambiguous(UPPERCASE_CHARS, no_ambiguous));
+        }
+
+        if self.contains(CharacterSet::LOWERCASE) {
+            letters.extend(filter_ambiguous(LOWERCASE_CHARS, no_ambiguous));
+        }
+
+        if !letters.is_empty() {
+            pools.push(letters);
+        }
+
+        if self.contains(CharacterSet::NUMBERS) {
+            pools.push(filter_ambiguous(NUMBER_CHARS, no_ambiguous));
+        }
+
+        if self.contains(CharacterSet::SYMBOLS) {
+            pools.push(filter_ambiguous(SYMBOL_CHARS, no_ambiguous));
+        }
+
+        pools
+    }
+}
+
+/// 密码生成失败的原因。
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordError {
+    /// 请求的字符数少于需要保证出现的已启用字符类别数，无法满足"每个类别至少出现一次"的约束。
+    NotEnoughCharacters {
+        characters: u32,
+        required_classes: usize,
+    },
+}
+
+impl std::fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordError::NotEnoughCharacters {
+                characters,
+                required_classes,
+            } => write!(
+                f,
+                "cannot generate a {}-character password that contains all {} selected character classes",
+                characters, required_classes
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
+/// 按启用顺序返回 UPPERCASE/LOWERCASE/NUMBERS/SYMBOLS 四个字符类别中被选中的那些，
+/// 每个类别都必须在生成的密码中至少出现一次。当 `no_ambiguous` 为真时，
+/// 每个类别的字符池都会先剔除形近字符再返回。
+fn required_character_classes(character_set: CharacterSet, no_ambiguous: bool) -> Vec<Vec<char>> {
+    [
+        (CharacterSet::UPPERCASE, UPPERCASE_CHARS),
+        (CharacterSet::LOWERCASE, LOWERCASE_CHARS),
+        (CharacterSet::NUMBERS, NUMBER_CHARS),
+        (CharacterSet::SYMBOLS, SYMBOL_CHARS),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| character_set.contains(*flag))
+    .map(|(_, pool)| filter_ambiguous(pool, no_ambiguous))
+    .collect()
+}
+
+/// 确保 `password` 中每一个 `required_classes` 都至少出现一次，
+/// 做法是：对缺失的类别，随机挑选一个可以安全覆写的位置(即覆写后不会让另一个
+/// 已满足的类别重新跌回零次出现)，并用该类别的随机字符替换它。
+fn enforce_required_classes<R: Rng>(
+    rng: &mut R,
+    password: &mut [char],
+    required_classes: &[Vec<char>],
+) {
+    let class_of = |c: char| required_classes.iter().position(|pool| pool.contains(&c));
+
+    let mut counts: Vec<usize> = required_classes
+        .iter()
+        .map(|pool| password.iter().filter(|c| pool.contains(c)).count())
+        .collect();
+
+    for (class_index, pool) in required_classes.iter().enumerate() {
+        if counts[class_index] > 0 {
+            continue;
+        }
+
+        let position = (0..password.len())
+            .filter(|&i| match class_of(password[i]) {
+                Some(idx) => counts[idx] > 1,
+                None => true,
+            })
+            .choose(rng)
+            .expect("characters should be enough for the selected character classes");
+
+        if let Some(idx) = class_of(password[position]) {
+            counts[idx] -= 1;
+        }
+
+        password[position] = pool[rng.gen_range(0..pool.len())];
+        counts[class_index] += 1;
+    }
+}
+
+/// 生成具有指定长度和字符类别的随机密码。
+///
+/// 此函数创建具有所需字符数的随机密码，
+/// 密码所使用的字符类别由 `character_set` 中启用的标志决定。生成之后，
+/// 函数会确保每一个启用的字符类别(大写、小写、数字、符号)在密码中至少出现一次。
 ///
 /// # Arguments
 ///
 /// * `rng: &mut R` - 一个对随机数生成器的可变引用
 /// * `characters: u32` - 密码所需的字符数
-/// * `numbers: bool` - 指示密码中是否应包含数字的标志
-/// * `symbols: bool` - 指示密码中是否应包含符号的标志
+/// * `character_set: CharacterSet` - 密码中应包含的字符类别
+/// * `no_ambiguous: bool` - 指示是否应从字符池中剔除形近字符(如 'i'/'l'/'1'、'o'/'0')的标志
 ///
 /// # Returns
 ///
-/// * `String` - 生成的随机密码
+/// * `Result<String, PasswordError>` - 生成的随机密码，如果 `characters`
+///   小于已启用的字符类别数量，则返回错误
 ///
 /// # Examples
 ///
 /// ```
 /// use rand::thread_rng;
-/// use motus::random_password;
+/// use motus::{random_password, CharacterSet};
 ///
 /// let mut rng = thread_rng();
-/// let password = random_password(&mut rng, 12, true, true);
+/// let password = random_password(&mut rng, 12, CharacterSet::ALL, false).unwrap();
 /// assert_eq!(password.len(), 12);
 /// ```
 pub fn random_password<R: Rng>(
     rng: &mut R,
     characters: u32,
-    numbers: bool,
-    symbols: bool,
-) -> String {
-    let mut available_sets = vec![LETTER_CHARS];
+    character_set: CharacterSet,
+    no_ambiguous: bool,
+) -> Result<String, PasswordError> {
+    let available_sets = character_set.char_pools(no_ambiguous);
+    let required_classes = required_character_classes(character_set, no_ambiguous);
 
-    if numbers {
-        available_sets.push(NUMBER_CHARS);
+    if (characters as usize) < required_classes.len() {
+        return Err(PasswordError::NotEnoughCharacters {
+            characters,
+            required_classes: required_classes.len(),
+        });
     }
 
-    if symbols {
-        available_sets.push(SYMBOL_CHARS);
-    }
-
-    let weights: Vec<u32> = match (numbers,symbols) {
-
-        // 我们采用以下分布:70%字母，20%数字，10%符号。
-        (true, true) => vec![7,2,1],
-
-        // 确保我们应用以下分布:80%字母，20%数字
-        (true, false) => vec![8, 2],
-        (false, true) => vec![8, 2],
-
-        //确保应用以下分布:100%字母
-        (false, false) => vec![10],
-    };
+    // 我们采用以下分布(按 字母/数字/符号 的顺序): 70%字母，20%数字，10%符号，
+    // 并在某个类别未启用时跳过对应的权重，而不是简单地丢弃序列末尾的权重，
+    // 否则被跳过的中间类别会错误地继承排在它之后的类别的权重。
+    let weights: Vec<u32> = [
+        (character_set.intersects(CharacterSet::LETTERS), 7),
+        (character_set.contains(CharacterSet::NUMBERS), 2),
+        (character_set.contains(CharacterSet::SYMBOLS), 1),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, weight)| weight)
+    .collect();
 
     let dist_set = WeightedIndex::new(&weights).expect("weights should be valid");
-    let mut password = String::with_capacity(characters as usize);
+    let mut password: Vec<char> = Vec::with_capacity(characters as usize);
 
     for _ in 0..characters {
         let selected_set = available_sets
@@ -92,6 +291,111 @@ pub fn random_password<R: Rng>(
         password.push(selected_set[index]);
     }
 
-    password
+    enforce_required_classes(rng, &mut password, &required_classes);
+
+    Ok(password.into_iter().collect())
+}
 
+/// 生成由随机单词组成的、易于记忆的密码短语。
+///
+/// 此函数从内置的词表中均匀抽取 `word_count` 个单词，
+/// 并使用 `separator` 将它们拼接起来。可选地将每个单词的首字母大写，
+/// 并在短语中随机插入一个数字或符号，以提高其在 zxcvbn 等
+/// 基于模式的分析器下的评分。
+///
+/// # Arguments
+///
+/// * `rng: &mut R` - 一个对随机数生成器的可变引用
+/// * `word_count: u32` - 密码短语中应包含的单词数量
+/// * `separator: char` - 用于连接单词的分隔符
+/// * `capitalize: bool` - 指示是否应将每个单词的首字母大写的标志
+/// * `add_number_or_symbol: bool` - 指示是否应在密码短语中插入一个随机数字或符号的标志
+///
+/// # Returns
+///
+/// * `String` - 生成的密码短语
+///
+/// # Examples
+///
+/// ```
+/// use rand::thread_rng;
+/// use motus::memorable_password;
+///
+/// let mut rng = thread_rng();
+/// let password = memorable_password(&mut rng, 4, '-', true, true);
+/// assert_eq!(password.split('-').count(), 4);
+/// ```
+pub fn memorable_password<R: Rng>(
+    rng: &mut R,
+    word_count: u32,
+    separator: char,
+    capitalize: bool,
+    add_number_or_symbol: bool,
+) -> String {
+    let mut words: Vec<String> = (0..word_count)
+        .map(|_| {
+            let word = WORDLIST[rng.gen_range(0..WORDLIST.len())];
+
+            if capitalize {
+                capitalize_first_letter(word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+
+    if add_number_or_symbol {
+        let extra_set: &[char] = if rng.gen_bool(0.5) {
+            NUMBER_CHARS
+        } else {
+            SYMBOL_CHARS
+        };
+
+        let extra_char = extra_set[rng.gen_range(0..extra_set.len())];
+        let word_index = rng.gen_range(0..words.len());
+        let position = rng.gen_range(0..=words[word_index].len());
+        words[word_index].insert(position, extra_char);
+    }
+
+    words.join(&separator.to_string())
+}
+
+fn capitalize_first_letter(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enforce_required_classes_fills_missing_class() {
+        let mut rng = rand::thread_rng();
+        let required_classes = required_character_classes(CharacterSet::ALL, false);
+        let mut password: Vec<char> = "aaaaaaaa".chars().collect();
+
+        enforce_required_classes(&mut rng, &mut password, &required_classes);
+
+        for pool in &required_classes {
+            assert!(password.iter().any(|c| pool.contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_random_password_contains_every_selected_class() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..1000 {
+            let password = random_password(&mut rng, 16, CharacterSet::ALL, false).unwrap();
+            let chars: Vec<char> = password.chars().collect();
+
+            for pool in required_character_classes(CharacterSet::ALL, false) {
+                assert!(pool.iter().any(|c| chars.contains(c)));
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,11 @@
+use lazy_static::lazy_static;
+
+/// [`crate::memorable_password`] 用于构建 diceware 密码短语的 EFF 风格词表。
+///
+/// 该词表在编译期被直接打包进二进制文件，因此运行时不依赖磁盘上是否存在
+/// 词典文件。
+const WORDLIST_RAW: &str = include_str!("../assets/wordlist.txt");
+
+lazy_static! {
+    pub static ref WORDLIST: Vec<&'static str> = WORDLIST_RAW.lines().collect();
+}